@@ -1,17 +1,16 @@
 use rand::rngs::StdRng;
+use rand::Rng;
 use rand::SeedableRng;
 use rand::seq::SliceRandom;
+use rayon::prelude::*;
 use rusqlite::{params, Connection, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{self, BufRead};
-use tokio::task;
-use tokio::sync::Mutex;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
 const MAX_TRIES: usize = 100000000;
-const CONCURRENT_TASKS: usize = 13;
 
 #[derive(Debug, Clone)]
 struct OptimizationResult {
@@ -20,6 +19,269 @@ struct OptimizationResult {
     steps: usize,
 }
 
+/// Storage backend for discovered layouts.
+///
+/// Persistence used to be hardwired to a single `rusqlite::Connection`, so
+/// every lookup/insert serialized all optimizer tasks on SQLite. Hiding the
+/// store behind this trait lets short exploratory runs pick an all-in-RAM
+/// backend and only write to disk on exit.
+trait LayoutDB {
+    /// Returns `true` if `layout` has already been recorded.
+    fn contains(&self, layout: &[char]) -> bool;
+    /// Records a finished optimization result.
+    fn insert(&mut self, result: &OptimizationResult) -> Result<()>;
+    /// Returns the `n` lowest-cost layouts currently stored, cheapest first.
+    fn best_layouts(&self, n: usize) -> Vec<OptimizationResult>;
+    /// Persists any buffered state. A no-op for already-durable backends.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// SQLite-backed store: every call hits the database, matching the original
+/// on-disk behavior.
+struct SqliteLayoutDB {
+    conn: Connection,
+}
+
+impl SqliteLayoutDB {
+    fn new(conn: Connection) -> Self {
+        SqliteLayoutDB { conn }
+    }
+}
+
+impl LayoutDB for SqliteLayoutDB {
+    fn contains(&self, layout: &[char]) -> bool {
+        let existing: Result<String> = self.conn.query_row(
+            "SELECT layout FROM layouts WHERE layout = ?1",
+            params![layout.iter().collect::<String>()],
+            |row| row.get(0),
+        );
+        existing.is_ok()
+    }
+
+    fn insert(&mut self, result: &OptimizationResult) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO layouts (layout, cost, steps) VALUES (?1, ?2, ?3)",
+            params![
+                result.layout.iter().collect::<String>(),
+                result.cost,
+                result.steps
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn best_layouts(&self, n: usize) -> Vec<OptimizationResult> {
+        let mut stmt = match self
+            .conn
+            .prepare("SELECT layout, cost, steps FROM layouts ORDER BY cost ASC LIMIT ?1")
+        {
+            Ok(stmt) => stmt,
+            Err(_) => return Vec::new(),
+        };
+        let rows = stmt.query_map(params![n as i64], |row| {
+            let layout: String = row.get(0)?;
+            Ok(OptimizationResult {
+                layout: layout.chars().collect(),
+                cost: row.get(1)?,
+                steps: row.get::<_, i64>(2)? as usize,
+            })
+        });
+        match rows {
+            Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// In-memory store backed by a `HashSet` of layout strings and a `Vec` of
+/// results. The existing rows are read once at construction; after that nothing
+/// touches disk until [`flush`](LayoutDB::flush) replays the buffer into SQLite
+/// on exit, so exploratory runs pay no per-result I/O cost.
+struct MemoryLayoutDB {
+    conn: Connection,
+    seen: HashSet<String>,
+    results: Vec<OptimizationResult>,
+}
+
+impl MemoryLayoutDB {
+    fn new(conn: Connection) -> Self {
+        let mut seen = HashSet::new();
+        let mut results = Vec::new();
+        // Preload anything already on disk so `contains`/`best_layouts` agree
+        // with the durable store for the lifetime of the run.
+        if let Ok(mut stmt) = conn.prepare("SELECT layout, cost, steps FROM layouts") {
+            if let Ok(rows) = stmt.query_map([], |row| {
+                let layout: String = row.get(0)?;
+                Ok(OptimizationResult {
+                    layout: layout.chars().collect(),
+                    cost: row.get(1)?,
+                    steps: row.get::<_, i64>(2)? as usize,
+                })
+            }) {
+                for result in rows.flatten() {
+                    seen.insert(result.layout.iter().collect());
+                    results.push(result);
+                }
+            }
+        }
+        MemoryLayoutDB {
+            conn,
+            seen,
+            results,
+        }
+    }
+}
+
+impl LayoutDB for MemoryLayoutDB {
+    fn contains(&self, layout: &[char]) -> bool {
+        self.seen.contains(&layout.iter().collect::<String>())
+    }
+
+    fn insert(&mut self, result: &OptimizationResult) -> Result<()> {
+        if self.seen.insert(result.layout.iter().collect()) {
+            self.results.push(result.clone());
+        }
+        Ok(())
+    }
+
+    fn best_layouts(&self, n: usize) -> Vec<OptimizationResult> {
+        let mut sorted: Vec<OptimizationResult> = self.results.clone();
+        sorted.sort_by(|a, b| a.cost.partial_cmp(&b.cost).unwrap_or(std::cmp::Ordering::Equal));
+        sorted.truncate(n);
+        sorted
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        for result in &self.results {
+            // Rows already persisted in an earlier run collide on the UNIQUE
+            // index; ignore those and only write the freshly discovered ones.
+            self.conn.execute(
+                "INSERT OR IGNORE INTO layouts (layout, cost, steps) VALUES (?1, ?2, ?3)",
+                params![
+                    result.layout.iter().collect::<String>(),
+                    result.cost,
+                    result.steps
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Which hand a key is typed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hand {
+    Left,
+    Right,
+}
+
+/// A single physical key slot: where it sits and which finger reaches it.
+#[derive(Debug, Clone, Copy)]
+struct Key {
+    x: f64,
+    y: f64,
+    finger: u8,
+    hand: Hand,
+}
+
+/// Physical model of the 26 letter slots.
+///
+/// `calculate_cost` no longer assumes a single row; each slot has an `(x, y)`
+/// coordinate and a finger/hand assignment, and a bigram's cost is the
+/// weighted-Manhattan distance between its two keys plus a same-finger penalty
+/// and minus a hand-alternation bonus. The original 1D index model survives as
+/// the [`one_dimensional`](Geometry::one_dimensional) preset (all slots on one
+/// row, no penalties), so it reduces exactly to `freq * |pos_a - pos_b|`.
+#[derive(Clone)]
+struct Geometry {
+    keys: [Key; 26],
+    x_weight: f64,
+    y_weight: f64,
+    same_finger_penalty: f64,
+    alternation_bonus: f64,
+}
+
+impl Geometry {
+    /// The legacy single-row model: slot `i` sits at `(i, 0)` and carries no
+    /// finger or alternation effects, so cost matches the original 1D formula.
+    fn one_dimensional() -> Self {
+        let keys = std::array::from_fn(|i| Key {
+            x: i as f64,
+            y: 0.0,
+            finger: i as u8,
+            hand: if i < 13 { Hand::Left } else { Hand::Right },
+        });
+        Geometry {
+            keys,
+            x_weight: 1.0,
+            y_weight: 1.0,
+            same_finger_penalty: 0.0,
+            alternation_bonus: 0.0,
+        }
+    }
+
+    /// A QWERTY-style staggered 10×3 grid with standard finger assignments,
+    /// row jumps weighted more than lateral moves.
+    fn qwerty() -> Self {
+        // (x, y, finger) per slot, in slot order: top row (10), home row (9),
+        // bottom row (7). Fingers 0-3 are the left hand, 6-9 the right.
+        let spec: [(f64, f64, u8); 26] = [
+            // top row: q w e r t y u i o p
+            (0.0, 0.0, 0), (1.0, 0.0, 1), (2.0, 0.0, 2), (3.0, 0.0, 3), (4.0, 0.0, 3),
+            (5.0, 0.0, 6), (6.0, 0.0, 6), (7.0, 0.0, 7), (8.0, 0.0, 8), (9.0, 0.0, 9),
+            // home row: a s d f g h j k l
+            (0.25, 1.0, 0), (1.25, 1.0, 1), (2.25, 1.0, 2), (3.25, 1.0, 3), (4.25, 1.0, 3),
+            (5.25, 1.0, 6), (6.25, 1.0, 6), (7.25, 1.0, 7), (8.25, 1.0, 8),
+            // bottom row: z x c v b n m
+            (0.75, 2.0, 0), (1.75, 2.0, 1), (2.75, 2.0, 2), (3.75, 2.0, 3), (4.75, 2.0, 3),
+            (5.75, 2.0, 6), (6.75, 2.0, 6),
+        ];
+        let keys = std::array::from_fn(|i| {
+            let (x, y, finger) = spec[i];
+            Key {
+                x,
+                y,
+                finger,
+                hand: if finger < 5 { Hand::Left } else { Hand::Right },
+            }
+        });
+        Geometry {
+            keys,
+            x_weight: 1.0,
+            y_weight: 2.0,
+            same_finger_penalty: 3.0,
+            alternation_bonus: 1.0,
+        }
+    }
+
+    /// Selects the geometry from the CLI arguments: `--qwerty` uses the 2D
+    /// staggered grid, anything else keeps the 1D index model.
+    fn from_args() -> Self {
+        if std::env::args().any(|arg| arg == "--qwerty") {
+            Geometry::qwerty()
+        } else {
+            Geometry::one_dimensional()
+        }
+    }
+
+    /// Typing effort of a bigram whose letters occupy slots `pos_a` and
+    /// `pos_b`, before multiplying by the bigram's frequency.
+    fn pair_cost(&self, pos_a: usize, pos_b: usize) -> f64 {
+        let (ka, kb) = (&self.keys[pos_a], &self.keys[pos_b]);
+        let mut cost =
+            (ka.x - kb.x).abs() * self.x_weight + (ka.y - kb.y).abs() * self.y_weight;
+        if ka.finger == kb.finger && pos_a != pos_b {
+            cost += self.same_finger_penalty;
+        }
+        if ka.hand != kb.hand {
+            cost -= self.alternation_bonus;
+        }
+        cost
+    }
+}
+
 fn load_bigram_frequencies(filename: &str) -> io::Result<HashMap<(char, char), f64>> {
     let file = File::open(filename)?;
     let reader = io::BufReader::new(file);
@@ -40,59 +302,93 @@ fn load_bigram_frequencies(filename: &str) -> io::Result<HashMap<(char, char), f
     Ok(bigrams)
 }
 
-fn calculate_cost(layout: &[char], bigram_freq: &HashMap<(char, char), f64>) -> f64 {
+fn calculate_cost(
+    layout: &[char],
+    bigram_freq: &HashMap<(char, char), f64>,
+    geometry: &Geometry,
+) -> f64 {
     bigram_freq.iter().fold(0.0, |mut cost, ((a, b), freq)| {
         if let (Some(pos_a), Some(pos_b)) = (
             layout.iter().position(|&x| x == *a),
             layout.iter().position(|&x| x == *b),
         ) {
-            cost += (*freq) * (pos_a.abs_diff(pos_b) as f64);
+            cost += (*freq) * geometry.pair_cost(pos_a, pos_b);
         }
         cost
     })
 }
 
+/// Per-letter bigram index supporting O(changed-bigrams) cost updates.
+///
+/// A single swap moves only two letters, so only bigrams that mention one of
+/// them change cost. This maps each letter to the bigrams it participates in,
+/// letting [`swap_cost_delta`] recompute just those instead of iterating the
+/// whole frequency map per candidate swap.
+struct BigramIndex {
+    per_letter: HashMap<char, Vec<(char, char, f64)>>,
+}
+
+impl BigramIndex {
+    fn build(bigram_freq: &HashMap<(char, char), f64>) -> Self {
+        let mut per_letter: HashMap<char, Vec<(char, char, f64)>> = HashMap::new();
+        for ((a, b), freq) in bigram_freq {
+            per_letter.entry(*a).or_default().push((*a, *b, *freq));
+            if a != b {
+                per_letter.entry(*b).or_default().push((*a, *b, *freq));
+            }
+        }
+        BigramIndex { per_letter }
+    }
+
+    fn bigrams_of(&self, letter: char) -> &[(char, char, f64)] {
+        self.per_letter.get(&letter).map_or(&[], |v| v.as_slice())
+    }
+}
+
 fn generate_random_layout(rng: &mut StdRng) -> Vec<char> {
     let mut layout: Vec<char> = ALPHABET.chars().collect();
     layout.shuffle(rng);
     layout
 }
 
-async fn layout_exists(conn: Arc<Mutex<Connection>>, layout: &[char]) -> bool {
-    let conn = conn.lock().await;
-    let existing: Result<String> = conn.query_row(
-        "SELECT layout FROM layouts WHERE layout = ?1",
-        params![layout.iter().collect::<String>()],
-        |row| row.get(0),
-    );
-    existing.is_ok()
-}
+/// Mask in which every position is mutable — the default unrestricted search.
+const ALL_MUTABLE: [bool; 26] = [true; 26];
 
 fn find_valley(
     mut layout: Vec<char>,
     bigram_freq: &HashMap<(char, char), f64>,
+    index: &BigramIndex,
+    geometry: &Geometry,
+    mutable: &[bool; 26],
 ) -> OptimizationResult {
-    let mut current_cost = calculate_cost(&layout, bigram_freq);
+    let mut pos: HashMap<char, usize> =
+        layout.iter().enumerate().map(|(idx, &c)| (c, idx)).collect();
+    let mut current_cost = calculate_cost(&layout, bigram_freq, geometry);
     let mut steps = 0;
     loop {
         let mut best_swap = None;
-        let mut best_swap_cost = current_cost;
+        // Improving swaps have a negative cost delta; 0.0 is the accept floor.
+        let mut best_delta = 0.0;
 
         for i in 0..26 {
             for j in i + 1..26 {
-                layout.swap(i, j);
-                let new_cost = calculate_cost(&layout, bigram_freq);
-                if new_cost < best_swap_cost {
+                // Skip swaps that would move a pinned key.
+                if !mutable[i] || !mutable[j] {
+                    continue;
+                }
+                let delta = swap_cost_delta(&layout, i, j, &pos, index, geometry);
+                if delta < best_delta {
                     best_swap = Some((i, j));
-                    best_swap_cost = new_cost;
+                    best_delta = delta;
                 }
-                layout.swap(i, j);
             }
         }
         steps += 1;
         if let Some((i, j)) = best_swap {
             layout.swap(i, j);
-            current_cost = best_swap_cost;
+            pos.insert(layout[i], i);
+            pos.insert(layout[j], j);
+            current_cost += best_delta;
         } else {
             return OptimizationResult {
                 layout,
@@ -103,16 +399,520 @@ fn find_valley(
     }
 }
 
-async fn save_to_db(conn: Arc<Mutex<Connection>>, result: OptimizationResult) -> Result<()> {
-    if layout_exists(conn.clone(), &result.layout).await {
+/// Change in total cost from swapping the letters at positions `i` and `j`,
+/// evaluated without recosting the whole layout.
+///
+/// Only bigrams that touch one of the two swapped letters change their
+/// contribution, so we skip every other entry in `bigram_freq`. `pos` maps
+/// each letter to its current index in `layout`.
+fn swap_cost_delta(
+    layout: &[char],
+    i: usize,
+    j: usize,
+    pos: &HashMap<char, usize>,
+    index: &BigramIndex,
+    geometry: &Geometry,
+) -> f64 {
+    let (la, lb) = (layout[i], layout[j]);
+
+    // Contribution change of one bigram (a, b) under the swap la<->lb.
+    let contribution = |a: char, b: char, freq: f64| -> f64 {
+        let (pa_old, pb_old) = match (pos.get(&a), pos.get(&b)) {
+            (Some(&pa), Some(&pb)) => (pa, pb),
+            _ => return 0.0,
+        };
+        let pa_new = if a == la { j } else if a == lb { i } else { pa_old };
+        let pb_new = if b == la { j } else if b == lb { i } else { pb_old };
+        freq * (geometry.pair_cost(pa_new, pb_new) - geometry.pair_cost(pa_old, pb_old))
+    };
+
+    let mut delta = 0.0;
+    for &(a, b, freq) in index.bigrams_of(la) {
+        delta += contribution(a, b, freq);
+    }
+    // Process lb's bigrams, skipping any that also touch la — those were
+    // already counted in the loop above.
+    for &(a, b, freq) in index.bigrams_of(lb) {
+        if a == la || b == la {
+            continue;
+        }
+        delta += contribution(a, b, freq);
+    }
+    delta
+}
+
+/// Tunable parameters for [`anneal`].
+#[derive(Debug, Clone)]
+struct AnnealParams {
+    /// Starting temperature; higher accepts more uphill moves early on.
+    start_temp: f64,
+    /// Temperature floor at which cooling stops.
+    min_temp: f64,
+    /// Geometric cooling factor applied each step (`T *= alpha`).
+    alpha: f64,
+}
+
+impl Default for AnnealParams {
+    fn default() -> Self {
+        AnnealParams {
+            start_temp: 10.0,
+            min_temp: 1e-3,
+            alpha: 0.9995,
+        }
+    }
+}
+
+/// Simulated-annealing optimizer over the same single-swap neighborhood as
+/// [`find_valley`].
+///
+/// Unlike steepest descent, uphill swaps are accepted with probability
+/// `exp(-delta / T)`, letting the search climb out of the local minima that
+/// trap every greedy restart. Temperature cools geometrically from
+/// `start_temp` to `min_temp`; the best layout ever visited is tracked apart
+/// from the working state so the returned result is the global best seen, not
+/// wherever the walk happened to end.
+fn anneal(
+    mut layout: Vec<char>,
+    bigram_freq: &HashMap<(char, char), f64>,
+    index: &BigramIndex,
+    geometry: &Geometry,
+    params: &AnnealParams,
+    mutable: &[bool; 26],
+    rng: &mut StdRng,
+) -> OptimizationResult {
+    let mut pos: HashMap<char, usize> =
+        layout.iter().enumerate().map(|(idx, &c)| (c, idx)).collect();
+    let mut current_cost = calculate_cost(&layout, bigram_freq, geometry);
+
+    let mut best_layout = layout.clone();
+    let mut best_cost = current_cost;
+
+    // Only unpinned positions are eligible for a swap; nothing moves if fewer
+    // than two slots are mutable.
+    let movable: Vec<usize> = (0..26).filter(|&i| mutable[i]).collect();
+    if movable.len() < 2 {
+        return OptimizationResult {
+            layout,
+            cost: current_cost,
+            steps: 0,
+        };
+    }
+
+    let mut temp = params.start_temp;
+    let mut steps = 0;
+    while temp > params.min_temp {
+        let i = movable[rng.random_range(0..movable.len())];
+        let mut j = movable[rng.random_range(0..movable.len())];
+        while j == i {
+            j = movable[rng.random_range(0..movable.len())];
+        }
+
+        let delta = swap_cost_delta(&layout, i, j, &pos, index, geometry);
+        if delta <= 0.0 || rng.random::<f64>() < (-delta / temp).exp() {
+            layout.swap(i, j);
+            pos.insert(layout[i], i);
+            pos.insert(layout[j], j);
+            current_cost += delta;
+            if current_cost < best_cost {
+                best_cost = current_cost;
+                best_layout = layout.clone();
+            }
+        }
+
+        temp *= params.alpha;
+        steps += 1;
+    }
+
+    OptimizationResult {
+        layout: best_layout,
+        cost: best_cost,
+        steps,
+    }
+}
+
+/// Order crossover (OX) of two 26-letter permutations, restricted to the
+/// positions marked mutable in `mutable`.
+///
+/// Pinned positions are copied straight from `parent_a` (both parents agree on
+/// them), so anchors survive untouched. Among the mutable slots a contiguous
+/// slice between two random cut points is copied verbatim from `parent_a`; the
+/// rest are filled with the mutable letters of `parent_b` in the order they
+/// appear there, skipping any already placed. The result is always a valid
+/// permutation of the full alphabet.
+fn order_crossover(
+    parent_a: &[char],
+    parent_b: &[char],
+    mutable: &[bool; 26],
+    rng: &mut StdRng,
+) -> Vec<char> {
+    // Start from `parent_a` so every pinned position is already correct.
+    let mut child: Vec<char> = parent_a.to_vec();
+    let movable: Vec<usize> = (0..parent_a.len()).filter(|&i| mutable[i]).collect();
+    let m = movable.len();
+    if m == 0 {
+        return child;
+    }
+
+    let mut cut1 = rng.random_range(0..m);
+    let mut cut2 = rng.random_range(0..m);
+    if cut1 > cut2 {
+        std::mem::swap(&mut cut1, &mut cut2);
+    }
+
+    let mut placed: HashSet<char> = HashSet::new();
+    for k in cut1..=cut2 {
+        placed.insert(parent_a[movable[k]]);
+    }
+
+    let mut idx = (cut2 + 1) % m;
+    for off in 0..m {
+        let gene = parent_b[movable[(cut2 + 1 + off) % m]];
+        if placed.insert(gene) {
+            child[movable[idx]] = gene;
+            idx = (idx + 1) % m;
+        }
+    }
+    child
+}
+
+/// Applies a swap mutation to `layout` with probability `rate`, swapping only
+/// among the positions marked mutable in `mutable`.
+fn swap_mutation(layout: &mut [char], rate: f64, mutable: &[bool; 26], rng: &mut StdRng) {
+    if rng.random::<f64>() < rate {
+        let movable: Vec<usize> = (0..layout.len()).filter(|&i| mutable[i]).collect();
+        if movable.len() < 2 {
+            return;
+        }
+        let i = movable[rng.random_range(0..movable.len())];
+        let j = movable[rng.random_range(0..movable.len())];
+        layout.swap(i, j);
+    }
+}
+
+/// Tunable parameters for [`genetic`].
+#[derive(Debug, Clone)]
+struct GeneticParams {
+    /// Number of layouts carried each generation.
+    population_size: usize,
+    /// How many generations to evolve.
+    generations: usize,
+    /// Per-child probability of a swap mutation.
+    mutation_rate: f64,
+    /// Top layouts copied unchanged into the next generation.
+    elite_count: usize,
+    /// Whether to hill-climb each child with [`find_valley`] (memetic search).
+    memetic: bool,
+}
+
+impl Default for GeneticParams {
+    fn default() -> Self {
+        GeneticParams {
+            population_size: 64,
+            generations: 200,
+            mutation_rate: 0.1,
+            elite_count: 4,
+            memetic: false,
+        }
+    }
+}
+
+/// Population-based optimizer that recombines whole layouts instead of only
+/// restarting and hill-climbing.
+///
+/// The initial population is `seeds` (typically the best DB rows plus random
+/// layouts) topped up to `population_size` with fresh random layouts. Each
+/// generation keeps the elites, then breeds the rest by rank-selecting parents
+/// (lower cost favored), crossing them with [`order_crossover`], mutating, and
+/// optionally hill-climbing. Returned is the final population sorted cheapest
+/// first, ready to be inserted back into the DB.
+fn genetic(
+    bigram_freq: &HashMap<(char, char), f64>,
+    index: &BigramIndex,
+    geometry: &Geometry,
+    seeds: Vec<Vec<char>>,
+    params: &GeneticParams,
+    mutable: &[bool; 26],
+    pinned_base: Option<&[char]>,
+    rng: &mut StdRng,
+) -> Vec<OptimizationResult> {
+    let mut population: Vec<Vec<char>> = seeds;
+    while population.len() < params.population_size {
+        population.push(fresh_layout(pinned_base, mutable, rng));
+    }
+    population.truncate(params.population_size);
+
+    // Rank-selection helper: bias the draw toward the front of the (sorted)
+    // population so lower-cost parents reproduce more often.
+    let select = |rng: &mut StdRng, pop: usize| -> usize {
+        (rng.random::<f64>().powi(2) * pop as f64) as usize % pop
+    };
+
+    let mut scored: Vec<(Vec<char>, f64)> = population
+        .into_iter()
+        .map(|l| {
+            let cost = calculate_cost(&l, bigram_freq, geometry);
+            (l, cost)
+        })
+        .collect();
+
+    for _ in 0..params.generations {
+        scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut next: Vec<(Vec<char>, f64)> = scored
+            .iter()
+            .take(params.elite_count.min(scored.len()))
+            .cloned()
+            .collect();
+
+        while next.len() < params.population_size {
+            let pa = &scored[select(rng, scored.len())].0;
+            let pb = &scored[select(rng, scored.len())].0;
+            let mut child = order_crossover(pa, pb, mutable, rng);
+            swap_mutation(&mut child, params.mutation_rate, mutable, rng);
+            if params.memetic {
+                child = find_valley(child, bigram_freq, index, geometry, mutable).layout;
+            }
+            let cost = calculate_cost(&child, bigram_freq, geometry);
+            next.push((child, cost));
+        }
+
+        scored = next;
+    }
+
+    scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored
+        .into_iter()
+        .map(|(layout, cost)| OptimizationResult {
+            layout,
+            cost,
+            steps: params.generations,
+        })
+        .collect()
+}
+
+/// A layout paired with its cost, ordered by cost so it can live in a
+/// `BinaryHeap`. Ties break on the layout string to keep the ordering total.
+#[derive(Debug, Clone, PartialEq)]
+struct BeamCandidate {
+    cost: f64,
+    layout: Vec<char>,
+}
+
+impl Eq for BeamCandidate {}
+
+impl Ord for BeamCandidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.cost
+            .partial_cmp(&other.cost)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| self.layout.cmp(&other.layout))
+    }
+}
+
+impl PartialOrd for BeamCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Tunable parameters for [`beam_search`].
+#[derive(Debug, Clone)]
+struct BeamParams {
+    /// Beam width: how many layouts survive each round.
+    width: usize,
+    /// How many best improving swaps to expand per beam member.
+    best_k: usize,
+    /// Round budget before the search gives up.
+    max_steps: usize,
+}
+
+impl Default for BeamParams {
+    fn default() -> Self {
+        BeamParams {
+            width: 16,
+            best_k: 8,
+            max_steps: 1000,
+        }
+    }
+}
+
+/// Beam-search optimizer: a width-`W` frontier of hill climbs run in lockstep.
+///
+/// Each round every beam member is expanded into its `best_k` cheapest
+/// improving swaps; all resulting candidates are pooled, deduplicated by
+/// layout string, and the `width` lowest-cost distinct ones are kept via a
+/// min-heap (`BinaryHeap` of [`Reverse`](std::cmp::Reverse)). The search stops
+/// once no candidate beats the current beam's best, or after `max_steps`
+/// rounds. Exploring several basins at once tends to beat the same budget
+/// spent on independent greedy restarts.
+fn beam_search(
+    bigram_freq: &HashMap<(char, char), f64>,
+    index: &BigramIndex,
+    geometry: &Geometry,
+    seeds: Vec<Vec<char>>,
+    params: &BeamParams,
+    mutable: &[bool; 26],
+    pinned_base: Option<&[char]>,
+    rng: &mut StdRng,
+) -> OptimizationResult {
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    let mut beam: Vec<BeamCandidate> = seeds
+        .into_iter()
+        .map(|layout| {
+            let cost = calculate_cost(&layout, bigram_freq, geometry);
+            BeamCandidate { cost, layout }
+        })
+        .collect();
+    while beam.len() < params.width {
+        let layout = fresh_layout(pinned_base, mutable, rng);
+        let cost = calculate_cost(&layout, bigram_freq, geometry);
+        beam.push(BeamCandidate { cost, layout });
+    }
+    beam.truncate(params.width);
+
+    let mut best = beam
+        .iter()
+        .min()
+        .cloned()
+        .unwrap_or_else(|| BeamCandidate { cost: f64::INFINITY, layout: Vec::new() });
+
+    let mut steps = 0;
+    for _ in 0..params.max_steps {
+        steps += 1;
+        // Dedup the round's candidate pool by layout string.
+        let mut pool: HashMap<String, BeamCandidate> = HashMap::new();
+        // Carrying the current beam forward keeps good members when expansions
+        // are weak, so the beam never regresses.
+        for cand in &beam {
+            pool.insert(cand.layout.iter().collect(), cand.clone());
+        }
+
+        for cand in &beam {
+            let pos: HashMap<char, usize> = cand
+                .layout
+                .iter()
+                .enumerate()
+                .map(|(idx, &c)| (c, idx))
+                .collect();
+            let mut improving: Vec<(f64, usize, usize)> = Vec::new();
+            for i in 0..26 {
+                for j in i + 1..26 {
+                    if !mutable[i] || !mutable[j] {
+                        continue;
+                    }
+                    let delta = swap_cost_delta(&cand.layout, i, j, &pos, index, geometry);
+                    if delta < 0.0 {
+                        improving.push((cand.cost + delta, i, j));
+                    }
+                }
+            }
+            improving.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+            for &(new_cost, i, j) in improving.iter().take(params.best_k) {
+                let mut layout = cand.layout.clone();
+                layout.swap(i, j);
+                pool.entry(layout.iter().collect())
+                    .or_insert(BeamCandidate { cost: new_cost, layout });
+            }
+        }
+
+        // Keep the `width` lowest-cost distinct candidates via a min-heap.
+        let mut heap: BinaryHeap<Reverse<BeamCandidate>> =
+            pool.into_values().map(Reverse).collect();
+        let mut next = Vec::with_capacity(params.width);
+        while next.len() < params.width {
+            match heap.pop() {
+                Some(Reverse(cand)) => next.push(cand),
+                None => break,
+            }
+        }
+
+        let round_best = next
+            .iter()
+            .min()
+            .cloned()
+            .unwrap_or_else(|| best.clone());
+        // No candidate improved on the incumbent: the frontier has converged.
+        if round_best.cost >= best.cost {
+            break;
+        }
+        best = round_best;
+        beam = next;
+    }
+
+    OptimizationResult {
+        layout: best.layout,
+        cost: best.cost,
+        steps,
+    }
+}
+
+/// Which local optimizer a run drives each restart through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Optimizer {
+    /// Steepest-descent hill climb ([`find_valley`]).
+    Valley,
+    /// Simulated annealing ([`anneal`]).
+    Anneal,
+    /// Population-based genetic search ([`genetic`]).
+    Genetic,
+    /// Width-W beam search ([`beam_search`]).
+    Beam,
+}
+
+impl Optimizer {
+    /// Selects the optimizer from the CLI arguments: `--anneal` picks
+    /// simulated annealing, `--genetic` the population search, `--beam` the
+    /// beam search, anything else keeps the greedy hill climb.
+    fn from_args() -> Self {
+        if std::env::args().any(|arg| arg == "--anneal") {
+            Optimizer::Anneal
+        } else if std::env::args().any(|arg| arg == "--genetic") {
+            Optimizer::Genetic
+        } else if std::env::args().any(|arg| arg == "--beam") {
+            Optimizer::Beam
+        } else {
+            Optimizer::Valley
+        }
+    }
+
+    /// Runs one optimization pass from `layout` under this optimizer.
+    fn optimize(
+        self,
+        layout: Vec<char>,
+        bigram_freq: &HashMap<(char, char), f64>,
+        index: &BigramIndex,
+        geometry: &Geometry,
+        mutable: &[bool; 26],
+        rng: &mut StdRng,
+    ) -> OptimizationResult {
+        match self {
+            Optimizer::Valley => find_valley(layout, bigram_freq, index, geometry, mutable),
+            Optimizer::Anneal => anneal(
+                layout,
+                bigram_freq,
+                index,
+                geometry,
+                &AnnealParams::default(),
+                mutable,
+                rng,
+            ),
+            // The genetic and beam optimizers run as their own whole-run
+            // passes in `main`; per-restart they degrade to a plain hill climb.
+            Optimizer::Genetic | Optimizer::Beam => {
+                find_valley(layout, bigram_freq, index, geometry, mutable)
+            }
+        }
+    }
+}
+
+fn save_to_db(db: &Arc<Mutex<Box<dyn LayoutDB + Send>>>, result: OptimizationResult) -> Result<()> {
+    let mut db = db.lock().unwrap();
+    if db.contains(&result.layout) {
         return Ok(());
     }
-    let conn = conn.lock().await;
-    conn.execute(
-        "INSERT INTO layouts (layout, cost, steps) VALUES (?1, ?2, ?3)",
-        params![result.layout.iter().collect::<String>(), result.cost, result.steps],
-    )?;
-    Ok(())
+    db.insert(&result)
 }
 
 fn setup_db() -> Result<Connection> {
@@ -129,36 +929,197 @@ steps INTEGER
     Ok(conn)
 }
 
-#[tokio::main]
-async fn main() -> io::Result<()> {
+/// Produces a random layout that keeps every pinned position of `base` in
+/// place, shuffling only the letters sitting in mutable slots.
+fn random_layout_respecting_pins(
+    base: &[char],
+    mutable: &[bool; 26],
+    rng: &mut StdRng,
+) -> Vec<char> {
+    let mut layout = base.to_vec();
+    let movable: Vec<usize> = (0..26).filter(|&i| mutable[i]).collect();
+    let mut letters: Vec<char> = movable.iter().map(|&i| base[i]).collect();
+    letters.shuffle(rng);
+    for (k, &i) in movable.iter().enumerate() {
+        layout[i] = letters[k];
+    }
+    layout
+}
+
+/// Produces a fresh random layout for seeding a population or beam, honoring
+/// pins when present: with a `pinned_base` the pinned positions keep their
+/// letters and only the mutable ones are reshuffled.
+fn fresh_layout(pinned_base: Option<&[char]>, mutable: &[bool; 26], rng: &mut StdRng) -> Vec<char> {
+    match pinned_base {
+        Some(base) => random_layout_respecting_pins(base, mutable, rng),
+        None => generate_random_layout(rng),
+    }
+}
+
+/// Builds the mutability mask, plus the layout to seed pinned runs from.
+///
+/// Without `--pinned` every position is mutable and there is no seed. With
+/// `--pinned` the best layout on record is pinned along its home row
+/// (positions 10..=18), so the search only refines the surrounding keys.
+fn pin_setup(db: &Arc<Mutex<Box<dyn LayoutDB + Send>>>) -> ([bool; 26], Option<Vec<char>>) {
+    if !std::env::args().any(|arg| arg == "--pinned") {
+        return (ALL_MUTABLE, None);
+    }
+    match db.lock().unwrap().best_layouts(1).into_iter().next() {
+        Some(best) => {
+            let mutable = std::array::from_fn(|i| !(10..=18).contains(&i));
+            (mutable, Some(best.layout))
+        }
+        None => {
+            eprintln!("--pinned requested but the database is empty; optimizing all keys");
+            (ALL_MUTABLE, None)
+        }
+    }
+}
+
+/// Picks the storage backend from the CLI arguments: `--memory` keeps
+/// everything in RAM and flushes on exit, anything else uses SQLite directly.
+fn select_backend() -> Box<dyn LayoutDB + Send> {
+    let conn = setup_db().expect("Failed to set up database");
+    if std::env::args().any(|arg| arg == "--memory") {
+        Box::new(MemoryLayoutDB::new(conn))
+    } else {
+        Box::new(SqliteLayoutDB::new(conn))
+    }
+}
+
+fn main() -> io::Result<()> {
     let bigram_freq = load_bigram_frequencies("bigrams.txt")?;
-    let conn = Arc::new(Mutex::new(setup_db().expect("Failed to set up database")));
-
-    let mut tasks = vec![];
-    for _ in 0..CONCURRENT_TASKS {
-        let bigram_freq = bigram_freq.clone();
-        let conn = Arc::clone(&conn);
-        tasks.push(task::spawn(async move {
-            for _ in 0..(MAX_TRIES / CONCURRENT_TASKS) {
-                let mut rng = StdRng::from_rng(&mut rand::rng());
-                let initial_layout = generate_random_layout(&mut rng);
-                let valley = find_valley(initial_layout, &bigram_freq);
-                if !layout_exists(conn.clone(), &valley.layout).await {
-                    println!(
-                        "Found valley: {:?} with cost: {}. Steps {}",
-                        valley.layout.iter().collect::<String>(),
-                        valley.cost,
-                        valley.steps,
-                    );
-                    save_to_db(conn.clone(), valley).await.map_err(|e| eprintln!("Failed to save to DB: {e}"));
+    let index = BigramIndex::build(&bigram_freq);
+    let db: Arc<Mutex<Box<dyn LayoutDB + Send>>> = Arc::new(Mutex::new(select_backend()));
+    let optimizer = Optimizer::from_args();
+    let geometry = Geometry::from_args();
+    let (mutable, pinned_base) = pin_setup(&db);
+
+    if optimizer == Optimizer::Genetic {
+        let params = GeneticParams::default();
+        let mut rng = StdRng::from_rng(&mut rand::rng());
+        // Seed half the population from pin-respecting random layouts when
+        // pinning, otherwise from the best layouts on record; `genetic` tops
+        // the rest up with fresh random layouts that keep any pins.
+        let seeds: Vec<Vec<char>> = match &pinned_base {
+            Some(base) => (0..params.population_size / 2)
+                .map(|_| random_layout_respecting_pins(base, &mutable, &mut rng))
+                .collect(),
+            None => db
+                .lock()
+                .unwrap()
+                .best_layouts(params.population_size / 2)
+                .into_iter()
+                .map(|r| r.layout)
+                .collect(),
+        };
+
+        let final_population = genetic(
+            &bigram_freq,
+            &index,
+            &geometry,
+            seeds,
+            &params,
+            &mutable,
+            pinned_base.as_deref(),
+            &mut rng,
+        );
+        // Write back only the elites, not the whole final population: the rest
+        // are mostly mediocre and would pollute the store the next run seeds
+        // from.
+        for result in final_population.into_iter().take(params.elite_count) {
+            let fresh = { !db.lock().unwrap().contains(&result.layout) };
+            if fresh {
+                println!(
+                    "Bred layout: {:?} with cost: {}. Generations {}",
+                    result.layout.iter().collect::<String>(),
+                    result.cost,
+                    result.steps,
+                );
+                if let Err(e) = save_to_db(&db, result) {
+                    eprintln!("Failed to save to DB: {e}");
                 }
             }
-        }));
+        }
+
+        db.lock().unwrap().flush().expect("Failed to flush database");
+        return Ok(());
     }
 
-    for t in tasks {
-        t.await.unwrap();
+    if optimizer == Optimizer::Beam {
+        let params = BeamParams::default();
+        let mut rng = StdRng::from_rng(&mut rand::rng());
+        // Start the frontier from pin-respecting random layouts when pinning,
+        // otherwise from the best layouts on record; `beam_search` tops the
+        // rest up with fresh random layouts that keep any pins.
+        let seeds: Vec<Vec<char>> = match &pinned_base {
+            Some(base) => (0..params.width)
+                .map(|_| random_layout_respecting_pins(base, &mutable, &mut rng))
+                .collect(),
+            None => db
+                .lock()
+                .unwrap()
+                .best_layouts(params.width)
+                .into_iter()
+                .map(|r| r.layout)
+                .collect(),
+        };
+
+        let best = beam_search(
+            &bigram_freq,
+            &index,
+            &geometry,
+            seeds,
+            &params,
+            &mutable,
+            pinned_base.as_deref(),
+            &mut rng,
+        );
+        let fresh = { !db.lock().unwrap().contains(&best.layout) };
+        if fresh {
+            println!(
+                "Beam best: {:?} with cost: {}. Rounds {}",
+                best.layout.iter().collect::<String>(),
+                best.cost,
+                best.steps,
+            );
+            if let Err(e) = save_to_db(&db, best) {
+                eprintln!("Failed to save to DB: {e}");
+            }
+        }
+
+        db.lock().unwrap().flush().expect("Failed to flush database");
+        return Ok(());
     }
 
+    // The search is pure number-crunching, so hand the restart seeds to a
+    // rayon work-stealing pool instead of async tasks. Each worker runs the
+    // selected optimizer from its own seed and pushes finished results through
+    // the `Mutex`-guarded DB.
+    (0..MAX_TRIES).into_par_iter().for_each(|seed| {
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let initial_layout = match &pinned_base {
+            Some(base) => random_layout_respecting_pins(base, &mutable, &mut rng),
+            None => generate_random_layout(&mut rng),
+        };
+        let valley =
+            optimizer.optimize(initial_layout, &bigram_freq, &index, &geometry, &mutable, &mut rng);
+        let fresh = { !db.lock().unwrap().contains(&valley.layout) };
+        if fresh {
+            println!(
+                "Found valley: {:?} with cost: {}. Steps {}",
+                valley.layout.iter().collect::<String>(),
+                valley.cost,
+                valley.steps,
+            );
+            if let Err(e) = save_to_db(&db, valley) {
+                eprintln!("Failed to save to DB: {e}");
+            }
+        }
+    });
+
+    db.lock().unwrap().flush().expect("Failed to flush database");
+
     Ok(())
 }